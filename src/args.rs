@@ -1,7 +1,7 @@
 #[derive(clap::Parser)]
 pub struct Arguments {
-    #[clap(long, default_value = "./db")]
-    pub db_path: String,
+    #[clap(long, default_value = "./db", multiple_occurrences(true))]
+    pub db_path: Vec<String>,
 
     #[clap(long, arg_enum, required(true))]
     pub db_type: DatabaseType,
@@ -23,10 +23,40 @@ pub struct Arguments {
 
     #[clap(long, default_value = "10")]
     pub readers_rate: usize,
+
+    #[clap(long)]
+    pub object_storage_endpoint: Option<String>,
+
+    #[clap(long)]
+    pub object_storage_bucket: Option<String>,
+
+    #[clap(long, default_value = "us-east-1")]
+    pub object_storage_region: String,
+
+    #[clap(long)]
+    pub object_storage_access_key: Option<String>,
+
+    #[clap(long)]
+    pub object_storage_secret_key: Option<String>,
+
+    #[clap(long)]
+    pub retention_window: Option<u64>,
+
+    #[clap(long)]
+    pub metrics_addr: Option<std::net::SocketAddr>,
+
+    #[clap(long)]
+    pub postgres_url: Option<String>,
+
+    #[clap(long, default_value = "10")]
+    pub postgres_pool_size: usize,
 }
 
 #[derive(clap::ArgEnum, Clone, Debug)]
 pub enum DatabaseType {
     Filesystem,
     RocksDB,
+    ObjectStorage,
+    Segmented,
+    Postgres,
 }
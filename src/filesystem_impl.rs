@@ -1,45 +1,72 @@
 use std::{path::PathBuf, str::FromStr};
 
-use super::FakeStorageImpl;
-use anyhow::{Context, Result};
+use super::{shard, FakeStorageImpl};
+use anyhow::Result;
 use async_trait::async_trait;
 
 #[derive(Clone)]
 pub struct FilesystemStorage {
-    path: PathBuf,
+    paths: Vec<PathBuf>,
 }
 
 impl FilesystemStorage {
-    pub fn new(path: &str) -> Result<Self> {
-        let path = PathBuf::from_str(path)?;
+    pub fn new(paths: &[String]) -> Result<Self> {
+        let paths = paths
+            .iter()
+            .map(|path| -> Result<PathBuf> {
+                let path = PathBuf::from_str(path)?;
+                std::fs::create_dir_all(&path)?;
+                Ok(path)
+            })
+            .collect::<Result<Vec<_>>>()?;
 
-        std::fs::create_dir_all(&path)?;
+        Ok(Self { paths })
+    }
 
-        Ok(Self { path })
+    fn dir_for(&self, camera: &str, index: u64) -> &PathBuf {
+        &self.paths[shard::pick(camera, index, self.paths.len())]
     }
 }
 
 #[async_trait]
 impl FakeStorageImpl for FilesystemStorage {
-    fn add_camera(&self, name: &str) {
-        let camera_path = self.path.join(name);
-        std::fs::create_dir_all(&camera_path).expect("mkdir should work");
+    async fn add_camera(&self, name: &str) {
+        for path in &self.paths {
+            let camera_path = path.join(name);
+            tokio::fs::create_dir_all(&camera_path)
+                .await
+                .expect("mkdir should work");
+        }
     }
 
     async fn write_image(&self, camera: &str, index: u64, image: &[u8]) -> Result<()> {
         let file_name = format!("{}.jpg", index);
-        let file_path = self.path.join(camera).join(file_name);
+        let file_path = self.dir_for(camera, index).join(camera).join(file_name);
 
-        std::fs::write(file_path, image)?;
+        tokio::fs::write(file_path, image).await?;
 
         Ok(())
     }
 
     async fn read_image(&self, camera: &str, index: u64) -> Result<Option<Vec<u8>>> {
         let file_name = format!("{}.jpg", index);
-        let file_path = self.path.join(camera).join(file_name);
+        let file_path = self.dir_for(camera, index).join(camera).join(file_name);
+
+        match tokio::fs::read(file_path).await {
+            Ok(image) => Ok(Some(image)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn delete_image(&self, camera: &str, index: u64) -> Result<()> {
+        let file_name = format!("{}.jpg", index);
+        let file_path = self.dir_for(camera, index).join(camera).join(file_name);
 
-        // TODO: should return Ok(None) if not found
-        Ok(Some(std::fs::read(file_path)?))
+        match tokio::fs::remove_file(file_path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
     }
 }
@@ -1,3 +1,5 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Result;
@@ -10,9 +12,10 @@ use tokio_stream::{wrappers::IntervalStream, StreamExt};
 
 #[async_trait]
 pub trait FakeStorageImpl: Clone + Send + Sync + 'static {
-    fn add_camera(&self, name: &str);
+    async fn add_camera(&self, name: &str);
     async fn write_image(&self, camera: &str, index: u64, image: &[u8]) -> Result<()>;
     async fn read_image(&self, camera: &str, index: u64) -> Result<Option<Vec<u8>>>;
+    async fn delete_image(&self, camera: &str, index: u64) -> Result<()>;
 }
 
 #[allow(dead_code)]
@@ -21,12 +24,29 @@ mod filesystem_impl;
 #[allow(dead_code)]
 mod rocksdb_impl;
 
+#[allow(dead_code)]
+mod object_storage_impl;
+
+#[allow(dead_code)]
+mod sled_impl;
+
+#[allow(dead_code)]
+mod segment_impl;
+
+#[allow(dead_code)]
+mod postgres_impl;
+
 mod args;
+mod metrics;
+mod shard;
 
 #[derive(Clone)]
 pub enum SupportedDatabase {
     Filesystem(filesystem_impl::FilesystemStorage),
     RocksDB(rocksdb_impl::RocksDB),
+    ObjectStorage(object_storage_impl::ObjectStorage),
+    Segmented(segment_impl::SegmentedStorage),
+    Postgres(postgres_impl::Postgres),
 }
 
 #[tokio::main]
@@ -40,14 +60,65 @@ async fn main() {
         args::DatabaseType::RocksDB => SupportedDatabase::RocksDB(
             rocksdb_impl::RocksDB::new(&args.db_path).expect("RocksDB::new"),
         ),
+        args::DatabaseType::ObjectStorage => {
+            SupportedDatabase::ObjectStorage(object_storage_impl::ObjectStorage::new(
+                args.object_storage_endpoint
+                    .as_deref()
+                    .expect("--object-storage-endpoint is required for db-type=object-storage"),
+                args.object_storage_bucket
+                    .as_deref()
+                    .expect("--object-storage-bucket is required for db-type=object-storage"),
+                &args.object_storage_region,
+                args.object_storage_access_key.as_deref().unwrap_or(""),
+                args.object_storage_secret_key.as_deref().unwrap_or(""),
+            )
+            .expect("ObjectStorage::new"))
+        }
+        args::DatabaseType::Segmented => SupportedDatabase::Segmented(
+            segment_impl::SegmentedStorage::new(
+                args.db_path
+                    .first()
+                    .expect("db-type=segmented requires at least one --db-path"),
+            )
+            .expect("SegmentedStorage::new"),
+        ),
+        args::DatabaseType::Postgres => SupportedDatabase::Postgres(
+            postgres_impl::Postgres::new(
+                args.postgres_url
+                    .as_deref()
+                    .expect("--postgres-url is required for db-type=postgres"),
+                args.postgres_pool_size,
+            )
+            .await
+            .expect("Postgres::new"),
+        ),
+    };
+
+    let backend: &'static str = match args.db_type {
+        args::DatabaseType::Filesystem => "filesystem",
+        args::DatabaseType::RocksDB => "rocksdb",
+        args::DatabaseType::ObjectStorage => "object_storage",
+        args::DatabaseType::Segmented => "segmented",
+        args::DatabaseType::Postgres => "postgres",
     };
 
+    let metrics = Arc::new(metrics::Metrics::new().expect("Metrics::new"));
+
+    if let Some(addr) = args.metrics_addr {
+        let metrics = metrics.clone();
+        tokio::task::spawn(async move {
+            if let Err(e) = metrics.serve(addr).await {
+                eprintln!("metrics server error: {:?}", e);
+            }
+        });
+    }
+
     let cameras: Vec<String> = (0..args.n_cameras)
         .map(|idx| format!("camera{}", idx))
         .collect();
 
     for camera in &cameras {
-        db.add_camera(camera);
+        db.add_camera(camera).await;
     }
 
     let writers_start_index: u64 = if args.seed_db {
@@ -68,7 +139,19 @@ async fn main() {
         (ptr, layout)
     };
 
-    let writers: Vec<JoinHandle<()>> = spawn_writers(db.clone(), &cameras, writers_start_index);
+    let write_progress: Vec<Arc<AtomicU64>> = cameras
+        .iter()
+        .map(|_| Arc::new(AtomicU64::new(writers_start_index)))
+        .collect();
+
+    let writers: Vec<JoinHandle<()>> = spawn_writers(
+        db.clone(),
+        &cameras,
+        writers_start_index,
+        &write_progress,
+        metrics.clone(),
+        backend,
+    );
     let readers: Vec<JoinHandle<()>> = spawn_readers(
         db.clone(),
         &cameras,
@@ -76,9 +159,28 @@ async fn main() {
         writers_start_index,
         args.n_readers,
         args.readers_rate,
+        metrics.clone(),
+        backend,
     );
+    let retention: Vec<JoinHandle<()>> = match args.retention_window {
+        Some(window) => spawn_retention(
+            db.clone(),
+            &cameras,
+            &write_progress,
+            window,
+            metrics.clone(),
+            backend,
+        ),
+        None => Vec::new(),
+    };
 
-    let _ = futures::future::join_all(writers.into_iter().chain(readers.into_iter())).await;
+    let _ = futures::future::join_all(
+        writers
+            .into_iter()
+            .chain(readers.into_iter())
+            .chain(retention.into_iter()),
+    )
+    .await;
 
     unsafe {
         std::alloc::dealloc(ptr, layout);
@@ -92,6 +194,8 @@ fn spawn_readers(
     end_index: u64,
     n_readers: usize,
     rate: usize,
+    metrics: Arc<metrics::Metrics>,
+    backend: &'static str,
 ) -> Vec<JoinHandle<()>> {
     (0..n_readers)
         .map(|_| {
@@ -102,10 +206,13 @@ fn spawn_readers(
                 .expect("choose");
 
             let db_cloned = db.clone();
+            let metrics = metrics.clone();
             tokio::task::spawn(async move {
-                read_images(db_cloned, &camera, start_index, end_index, rate)
-                    .await
-                    .expect("Read Camera 2 Images");
+                read_images(
+                    db_cloned, &camera, start_index, end_index, rate, metrics, backend,
+                )
+                .await
+                .expect("Read Camera 2 Images");
             })
         })
         .collect()
@@ -115,16 +222,54 @@ fn spawn_writers(
     db: impl FakeStorageImpl,
     cameras: &[String],
     start_index: u64,
+    progress: &[Arc<AtomicU64>],
+    metrics: Arc<metrics::Metrics>,
+    backend: &'static str,
 ) -> Vec<JoinHandle<()>> {
     cameras
         .iter()
-        .map(|camera| {
+        .zip(progress)
+        .map(|(camera, progress)| {
             let db_cloned = db.clone();
             let camera_name = camera.clone();
+            let progress = progress.clone();
+            let metrics = metrics.clone();
             tokio::task::spawn(async move {
-                write_camera_images(db_cloned, &camera_name, start_index)
+                write_camera_images(
+                    db_cloned,
+                    &camera_name,
+                    start_index,
+                    progress,
+                    metrics,
+                    backend,
+                )
+                .await
+                .expect("Write Camera 1 Images");
+            })
+        })
+        .collect()
+}
+
+fn spawn_retention(
+    db: impl FakeStorageImpl,
+    cameras: &[String],
+    progress: &[Arc<AtomicU64>],
+    window: u64,
+    metrics: Arc<metrics::Metrics>,
+    backend: &'static str,
+) -> Vec<JoinHandle<()>> {
+    cameras
+        .iter()
+        .zip(progress)
+        .map(|(camera, progress)| {
+            let db_cloned = db.clone();
+            let camera_name = camera.clone();
+            let progress = progress.clone();
+            let metrics = metrics.clone();
+            tokio::task::spawn(async move {
+                retain_camera_images(db_cloned, &camera_name, progress, window, metrics, backend)
                     .await
-                    .expect("Write Camera 1 Images");
+                    .expect("Retain Camera Images");
             })
         })
         .collect()
@@ -156,14 +301,14 @@ async fn read_images(
     start_index: u64,
     end_index: u64,
     rate: usize,
+    metrics: Arc<metrics::Metrics>,
+    backend: &str,
 ) -> Result<()> {
     let interval = tokio::time::interval_at(Instant::now(), Duration::from_secs(1) / rate as u32);
     let stream = IntervalStream::new(interval);
 
     pin_mut!(stream);
 
-    let mut max_duration = Duration::from_secs(0);
-
     println!("[{}] Starting read task", camera_name);
 
     while (stream.next().await).is_some() {
@@ -174,11 +319,7 @@ async fn read_images(
         let start = Instant::now();
         let _ = db.read_image(camera_name, index).await?;
 
-        let elapsed = start.elapsed();
-        if elapsed > max_duration {
-            max_duration = elapsed;
-            println!("[{}] Max read time: {:?}", camera_name, elapsed);
-        }
+        metrics.observe(backend, "read", start.elapsed());
     }
 
     Ok(())
@@ -188,6 +329,9 @@ async fn write_camera_images(
     db: impl FakeStorageImpl,
     camera_name: &str,
     start_index: u64,
+    progress: Arc<AtomicU64>,
+    metrics: Arc<metrics::Metrics>,
+    backend: &str,
 ) -> Result<()> {
     let start_time = Instant::now();
     let interval = tokio::time::interval_at(start_time, Duration::from_secs(1) / 20);
@@ -200,7 +344,6 @@ async fn write_camera_images(
         .collect();
 
     let mut index = start_index;
-    let mut max_duration = Duration::from_secs(0);
 
     println!("[{}] Starting write task", camera_name);
 
@@ -208,13 +351,46 @@ async fn write_camera_images(
         let start = Instant::now();
         db.write_image(camera_name, index, &jpeg_buffer).await?;
 
-        let elapsed = start.elapsed();
-        if elapsed > max_duration {
-            max_duration = elapsed;
-            println!("[{}] Max write time: {:?}", camera_name, elapsed);
-        }
+        metrics.observe(backend, "write", start.elapsed());
 
         index += 1;
+        progress.store(index, Ordering::Relaxed);
+    }
+
+    Ok(())
+}
+
+async fn retain_camera_images(
+    db: impl FakeStorageImpl,
+    camera_name: &str,
+    progress: Arc<AtomicU64>,
+    window: u64,
+    metrics: Arc<metrics::Metrics>,
+    backend: &str,
+) -> Result<()> {
+    let interval = tokio::time::interval(Duration::from_secs(1) / 20);
+    let stream = IntervalStream::new(interval);
+
+    pin_mut!(stream);
+
+    let mut next_to_delete = 0_u64;
+
+    println!(
+        "[{}] Starting retention task (window={})",
+        camera_name, window
+    );
+
+    while (stream.next().await).is_some() {
+        let written = progress.load(Ordering::Relaxed);
+
+        while next_to_delete + window < written {
+            let start = Instant::now();
+            db.delete_image(camera_name, next_to_delete).await?;
+
+            metrics.observe(backend, "delete", start.elapsed());
+
+            next_to_delete += 1;
+        }
     }
 
     Ok(())
@@ -222,10 +398,15 @@ async fn write_camera_images(
 
 #[async_trait]
 impl FakeStorageImpl for SupportedDatabase {
-    fn add_camera(&self, name: &str) {
+    async fn add_camera(&self, name: &str) {
         match self {
-            SupportedDatabase::Filesystem(fs) => fs.add_camera(name),
-            SupportedDatabase::RocksDB(rocks) => rocks.add_camera(name),
+            SupportedDatabase::Filesystem(fs) => fs.add_camera(name).await,
+            SupportedDatabase::RocksDB(rocks) => rocks.add_camera(name).await,
+            SupportedDatabase::ObjectStorage(object_storage) => {
+                object_storage.add_camera(name).await
+            }
+            SupportedDatabase::Segmented(segmented) => segmented.add_camera(name).await,
+            SupportedDatabase::Postgres(postgres) => postgres.add_camera(name).await,
         }
     }
 
@@ -233,6 +414,15 @@ impl FakeStorageImpl for SupportedDatabase {
         match self {
             SupportedDatabase::Filesystem(fs) => fs.write_image(camera, index, image).await,
             SupportedDatabase::RocksDB(rocks) => rocks.write_image(camera, index, image).await,
+            SupportedDatabase::ObjectStorage(object_storage) => {
+                object_storage.write_image(camera, index, image).await
+            }
+            SupportedDatabase::Segmented(segmented) => {
+                segmented.write_image(camera, index, image).await
+            }
+            SupportedDatabase::Postgres(postgres) => {
+                postgres.write_image(camera, index, image).await
+            }
         }
     }
 
@@ -240,6 +430,25 @@ impl FakeStorageImpl for SupportedDatabase {
         match self {
             SupportedDatabase::Filesystem(fs) => fs.read_image(camera, index).await,
             SupportedDatabase::RocksDB(rocks) => rocks.read_image(camera, index).await,
+            SupportedDatabase::ObjectStorage(object_storage) => {
+                object_storage.read_image(camera, index).await
+            }
+            SupportedDatabase::Segmented(segmented) => segmented.read_image(camera, index).await,
+            SupportedDatabase::Postgres(postgres) => postgres.read_image(camera, index).await,
+        }
+    }
+
+    async fn delete_image(&self, camera: &str, index: u64) -> Result<()> {
+        match self {
+            SupportedDatabase::Filesystem(fs) => fs.delete_image(camera, index).await,
+            SupportedDatabase::RocksDB(rocks) => rocks.delete_image(camera, index).await,
+            SupportedDatabase::ObjectStorage(object_storage) => {
+                object_storage.delete_image(camera, index).await
+            }
+            SupportedDatabase::Segmented(segmented) => {
+                segmented.delete_image(camera, index).await
+            }
+            SupportedDatabase::Postgres(postgres) => postgres.delete_image(camera, index).await,
         }
     }
 }
@@ -0,0 +1,88 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server};
+use prometheus::{Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder};
+
+/// Per-operation latency histograms and throughput counters, labeled by
+/// storage backend and operation (`read`/`write`/`delete`), exposed to Prometheus on
+/// a `/metrics` endpoint so p50/p99/p999 can be compared across backends in
+/// a single run.
+pub struct Metrics {
+    registry: Registry,
+    latency: HistogramVec,
+    ops: IntCounterVec,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new();
+
+        let latency = HistogramVec::new(
+            HistogramOpts::new(
+                "rocksdb_experiment_op_latency_seconds",
+                "Latency of storage backend operations",
+            )
+            .buckets(prometheus::exponential_buckets(0.0001, 2.0, 20)?),
+            &["backend", "operation"],
+        )?;
+
+        let ops = IntCounterVec::new(
+            Opts::new(
+                "rocksdb_experiment_ops_total",
+                "Number of completed storage backend operations",
+            ),
+            &["backend", "operation"],
+        )?;
+
+        registry.register(Box::new(latency.clone()))?;
+        registry.register(Box::new(ops.clone()))?;
+
+        Ok(Self {
+            registry,
+            latency,
+            ops,
+        })
+    }
+
+    pub fn observe(&self, backend: &str, operation: &str, elapsed: Duration) {
+        self.latency
+            .with_label_values(&[backend, operation])
+            .observe(elapsed.as_secs_f64());
+        self.ops.with_label_values(&[backend, operation]).inc();
+    }
+
+    pub async fn serve(&self, addr: SocketAddr) -> Result<()> {
+        let registry = self.registry.clone();
+
+        let make_svc = make_service_fn(move |_conn| {
+            let registry = registry.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |_req: Request<Body>| {
+                    let registry = registry.clone();
+                    async move {
+                        let encoder = TextEncoder::new();
+                        let metric_families = registry.gather();
+
+                        let mut buffer = Vec::new();
+                        encoder
+                            .encode(&metric_families, &mut buffer)
+                            .expect("encode metrics");
+
+                        Ok::<_, Infallible>(Response::new(Body::from(buffer)))
+                    }
+                }))
+            }
+        });
+
+        Server::bind(&addr)
+            .serve(make_svc)
+            .await
+            .context("metrics server failed")?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use super::FakeStorageImpl;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use aws_sdk_s3::config::{Credentials, Region};
+use aws_sdk_s3::Client;
+
+#[derive(Clone)]
+pub struct ObjectStorage {
+    client: Client,
+    bucket: String,
+}
+
+impl ObjectStorage {
+    pub fn new(
+        endpoint: &str,
+        bucket: &str,
+        region: &str,
+        access_key: &str,
+        secret_key: &str,
+    ) -> Result<Self> {
+        let credentials = Credentials::new(access_key, secret_key, None, None, "rocksdb-experiment");
+
+        let config = aws_sdk_s3::Config::builder()
+            .endpoint_url(endpoint)
+            .region(Region::new(region.to_owned()))
+            .credentials_provider(credentials)
+            .force_path_style(true)
+            .build();
+
+        Ok(Self {
+            client: Client::from_conf(config),
+            bucket: bucket.to_owned(),
+        })
+    }
+
+    fn object_key(camera: &str, index: u64) -> String {
+        format!("{}/{:06}.jpg", camera, index)
+    }
+}
+
+#[async_trait]
+impl FakeStorageImpl for ObjectStorage {
+    async fn add_camera(&self, _name: &str) {
+        // no-op: the camera name is baked into the object key prefix
+    }
+
+    async fn write_image(&self, camera: &str, index: u64, image: &[u8]) -> Result<()> {
+        let key = Self::object_key(camera, index);
+
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .body(image.to_owned().into())
+            .send()
+            .await
+            .context("put_object() failed")?;
+
+        Ok(())
+    }
+
+    async fn read_image(&self, camera: &str, index: u64) -> Result<Option<Vec<u8>>> {
+        let key = Self::object_key(camera, index);
+
+        let result = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await;
+
+        match result {
+            Ok(output) => {
+                let bytes = output
+                    .body
+                    .collect()
+                    .await
+                    .context("failed to collect get_object() body")?;
+
+                Ok(Some(bytes.into_bytes().to_vec()))
+            }
+            Err(aws_sdk_s3::error::SdkError::ServiceError(e)) if e.err().is_no_such_key() => {
+                Ok(None)
+            }
+            Err(e) => Err(e).context("get_object() failed"),
+        }
+    }
+
+    async fn delete_image(&self, camera: &str, index: u64) -> Result<()> {
+        let key = Self::object_key(camera, index);
+
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .context("delete_object() failed")?;
+
+        Ok(())
+    }
+}
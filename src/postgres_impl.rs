@@ -0,0 +1,99 @@
+use super::FakeStorageImpl;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use deadpool_postgres::{Config, Pool, Runtime};
+use tokio_postgres::NoTls;
+
+#[derive(Clone)]
+pub struct Postgres {
+    pool: Pool,
+}
+
+impl Postgres {
+    pub async fn new(url: &str, pool_size: usize) -> Result<Self> {
+        let mut config = Config::new();
+        config.url = Some(url.to_owned());
+        config.pool = Some(deadpool_postgres::PoolConfig::new(pool_size));
+
+        let pool = config
+            .create_pool(Some(Runtime::Tokio1), NoTls)
+            .context("Failed to create Postgres connection pool")?;
+
+        let client = pool.get().await.context("Failed to connect to Postgres")?;
+        client
+            .batch_execute(
+                "CREATE TABLE IF NOT EXISTS images (
+                    camera TEXT NOT NULL,
+                    index BIGINT NOT NULL,
+                    image BYTEA NOT NULL,
+                    PRIMARY KEY (camera, index)
+                )",
+            )
+            .await
+            .context("Failed to create images table")?;
+
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl FakeStorageImpl for Postgres {
+    async fn add_camera(&self, _name: &str) {
+        // no-op: all cameras share the single `images` table, keyed by (camera, index)
+    }
+
+    async fn write_image(&self, camera: &str, index: u64, image: &[u8]) -> Result<()> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("Failed to get Postgres connection")?;
+
+        client
+            .execute(
+                "INSERT INTO images (camera, index, image) VALUES ($1, $2, $3)
+                 ON CONFLICT (camera, index) DO UPDATE SET image = EXCLUDED.image",
+                &[&camera, &(index as i64), &image],
+            )
+            .await
+            .context("Failed to upsert image")?;
+
+        Ok(())
+    }
+
+    async fn read_image(&self, camera: &str, index: u64) -> Result<Option<Vec<u8>>> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("Failed to get Postgres connection")?;
+
+        let row = client
+            .query_opt(
+                "SELECT image FROM images WHERE camera = $1 AND index = $2",
+                &[&camera, &(index as i64)],
+            )
+            .await
+            .context("Failed to select image")?;
+
+        Ok(row.map(|row| row.get::<_, Vec<u8>>("image")))
+    }
+
+    async fn delete_image(&self, camera: &str, index: u64) -> Result<()> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("Failed to get Postgres connection")?;
+
+        client
+            .execute(
+                "DELETE FROM images WHERE camera = $1 AND index = $2",
+                &[&camera, &(index as i64)],
+            )
+            .await
+            .context("Failed to delete image")?;
+
+        Ok(())
+    }
+}
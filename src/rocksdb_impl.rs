@@ -1,32 +1,41 @@
 use std::sync::Arc;
 
-use super::FakeStorageImpl;
+use super::{shard, FakeStorageImpl};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
 use rocksdb::DB;
 
 #[derive(Clone)]
 pub struct RocksDB {
-    db: Arc<DB>,
+    dbs: Vec<Arc<DB>>,
 }
 
 impl RocksDB {
-    pub fn new(path: &str) -> Result<Self> {
-        Ok(Self {
-            db: create_with_existing_cf(path).context("Failed to create DB")?,
-        })
+    pub fn new(paths: &[String]) -> Result<Self> {
+        let dbs = paths
+            .iter()
+            .map(|path| create_with_existing_cf(path).context("Failed to create DB"))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { dbs })
+    }
+
+    fn db_for(&self, camera: &str, index: u64) -> &Arc<DB> {
+        &self.dbs[shard::pick(camera, index, self.dbs.len())]
     }
 }
 
 #[async_trait]
 impl FakeStorageImpl for RocksDB {
-    fn add_camera(&self, name: &str) {
-        let opts = rocksdb::Options::default();
-        let _ = self.db.create_cf(name, &opts);
+    async fn add_camera(&self, name: &str) {
+        for db in &self.dbs {
+            let opts = rocksdb::Options::default();
+            let _ = db.create_cf(name, &opts);
+        }
     }
 
     async fn write_image(&self, camera: &str, index: u64, image: &[u8]) -> Result<()> {
-        let db = self.db.clone();
+        let db = self.db_for(camera, index).clone();
         let camera = camera.to_owned();
         let key = format!("{}.jpg", index);
         let image = image.to_owned();
@@ -41,7 +50,7 @@ impl FakeStorageImpl for RocksDB {
     }
 
     async fn read_image(&self, camera: &str, index: u64) -> Result<Option<Vec<u8>>> {
-        let db = self.db.clone();
+        let db = self.db_for(camera, index).clone();
         let camera = camera.to_owned();
         let key = format!("{}.jpg", index);
 
@@ -51,6 +60,20 @@ impl FakeStorageImpl for RocksDB {
         })
         .await?
     }
+
+    async fn delete_image(&self, camera: &str, index: u64) -> Result<()> {
+        let db = self.db_for(camera, index).clone();
+        let camera = camera.to_owned();
+        let key = format!("{}.jpg", index);
+
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            let cf = db.cf_handle(&camera).context("Failed to open cf handle")?;
+            db.delete_cf(&cf, key).context("Failed to delete_cf()")?;
+
+            Ok(())
+        })
+        .await?
+    }
 }
 
 fn create_with_existing_cf(db_path: &str) -> Result<Arc<DB>, rocksdb::Error> {
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use super::FakeStorageImpl;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use tokio::fs::OpenOptions;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::Mutex;
+
+/// Frames for a camera are packed into concatenated segment files of this
+/// size (moonfire-nvr's "sample file" layout), instead of one file per JPEG,
+/// to cut the per-object inode overhead at the 150k-images-per-camera scale.
+const FRAMES_PER_SEGMENT: u64 = 512;
+
+#[derive(Clone, Copy, Default)]
+struct IndexEntry {
+    offset: u64,
+    length: u32,
+}
+
+#[derive(Clone)]
+pub struct SegmentedStorage {
+    path: PathBuf,
+    index: Arc<Mutex<HashMap<(String, u64), Vec<IndexEntry>>>>,
+}
+
+impl SegmentedStorage {
+    pub fn new(path: &str) -> Result<Self> {
+        let path = PathBuf::from(path);
+        std::fs::create_dir_all(&path)?;
+
+        Ok(Self {
+            path,
+            index: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    fn segment_file(&self, camera: &str, segment: u64) -> PathBuf {
+        self.path
+            .join(camera)
+            .join(format!("{:08}.segment", segment))
+    }
+}
+
+#[async_trait]
+impl FakeStorageImpl for SegmentedStorage {
+    async fn add_camera(&self, name: &str) {
+        let camera_path = self.path.join(name);
+        std::fs::create_dir_all(&camera_path).expect("mkdir should work");
+    }
+
+    async fn write_image(&self, camera: &str, index: u64, image: &[u8]) -> Result<()> {
+        let segment = index / FRAMES_PER_SEGMENT;
+        let slot = (index % FRAMES_PER_SEGMENT) as usize;
+
+        let file_path = self.segment_file(camera, segment);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&file_path)
+            .await
+            .context("failed to open segment file")?;
+
+        let offset = file.metadata().await?.len();
+        file.write_all(image)
+            .await
+            .context("failed to append frame")?;
+
+        let mut index_guard = self.index.lock().await;
+        let entries = index_guard
+            .entry((camera.to_owned(), segment))
+            .or_insert_with(|| vec![IndexEntry::default(); FRAMES_PER_SEGMENT as usize]);
+
+        entries[slot] = IndexEntry {
+            offset,
+            length: image.len() as u32,
+        };
+
+        Ok(())
+    }
+
+    async fn read_image(&self, camera: &str, index: u64) -> Result<Option<Vec<u8>>> {
+        let segment = index / FRAMES_PER_SEGMENT;
+        let slot = (index % FRAMES_PER_SEGMENT) as usize;
+
+        let entry = {
+            let index_guard = self.index.lock().await;
+            match index_guard.get(&(camera.to_owned(), segment)) {
+                Some(entries) => entries[slot],
+                None => return Ok(None),
+            }
+        };
+
+        if entry.length == 0 {
+            return Ok(None);
+        }
+
+        let file_path = self.segment_file(camera, segment);
+        let mut file = match tokio::fs::File::open(&file_path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        file.seek(std::io::SeekFrom::Start(entry.offset)).await?;
+
+        let mut buffer = vec![0_u8; entry.length as usize];
+        file.read_exact(&mut buffer)
+            .await
+            .context("failed to read frame")?;
+
+        Ok(Some(buffer))
+    }
+
+    async fn delete_image(&self, camera: &str, index: u64) -> Result<()> {
+        let segment = index / FRAMES_PER_SEGMENT;
+        let slot = (index % FRAMES_PER_SEGMENT) as usize;
+
+        // Segment files are only ever opened append-only and are never
+        // rewritten, so this drops the index entry but does not reclaim the
+        // underlying bytes; doing so would require compacting the segment.
+        let mut index_guard = self.index.lock().await;
+        if let Some(entries) = index_guard.get_mut(&(camera.to_owned(), segment)) {
+            entries[slot] = IndexEntry::default();
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,13 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Deterministically map a `(camera, index)` pair onto one of `n` shards
+/// (e.g. backing directories or database handles), so repeated benchmark
+/// runs spread writes across the same spindles the same way.
+pub(crate) fn pick(camera: &str, index: u64, n: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    camera.hash(&mut hasher);
+    index.hash(&mut hasher);
+
+    (hasher.finish() % n as u64) as usize
+}
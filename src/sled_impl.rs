@@ -25,7 +25,7 @@ impl Sled {
 
 #[async_trait]
 impl FakeStorageImpl for Sled {
-    fn add_camera(&self, name: &str) {
+    async fn add_camera(&self, name: &str) {
         let _ = self.db.open_tree(name);
     }
 
@@ -60,4 +60,18 @@ impl FakeStorageImpl for Sled {
         })
         .await?
     }
+
+    async fn delete_image(&self, camera: &str, index: u64) -> Result<()> {
+        let db = self.db.clone();
+        let camera = camera.to_owned();
+        let key = format!("{}.jpg", index);
+
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            db.open_tree(&camera)
+                .context("open_tree() failed")
+                .and_then(|tree| tree.remove(&key).context("tree.remove() failed"))
+                .map(|_| ())
+        })
+        .await?
+    }
 }